@@ -6,58 +6,201 @@
 use std::env;
 use std::fs::File;
 use std::io::{self, Read, BufReader};
-use std::collections::HashMap;
 
 #[derive(PartialEq,Copy,Clone,Debug)]
 enum Command {
     INCPTR { amount: isize },
     DECPTR { amount: isize },
-    INCVAL { amount: u8 },
-    DECVAL { amount: u8 },
+    INCVAL { amount: u32 },
+    DECVAL { amount: u32 },
     PUTC,
     GETC,
     LOOPSTART { end: usize },
     LOOPEND { start: usize },
+    SETVAL { amount: u8 },
+    MULADD { offset: isize, factor: i32 },
+    BREAK,
+}
+
+/// a contiguous tape of cells that can grow in both directions, addressed
+/// by a signed position relative to a movable base; cells are stored as
+/// u32 so the same Tape backs any configured cell width, with narrower
+/// widths masked down by the caller on every write (see `MemoryConfig`)
+struct Tape {
+    cells: Vec<u32>,
+    base: usize,
+}
+
+impl Tape {
+    fn new() -> Tape {
+        Tape { cells: vec![0; 1], base: 0 }
+    }
+
+    /// translate a tape position into a vector index, growing the
+    /// underlying storage on either end as needed
+    fn index(&mut self, pos: isize) -> usize {
+        let signed_index = pos + self.base as isize;
+        if signed_index < 0 {
+            let grow_by = (-signed_index) as usize;
+            let mut grown = vec![0; grow_by];
+            grown.extend_from_slice(&self.cells);
+            self.cells = grown;
+            self.base += grow_by;
+        }
+        let index = (pos + self.base as isize) as usize;
+        if index >= self.cells.len() {
+            self.cells.resize(index + 1, 0);
+        }
+        index
+    }
+
+    fn get(&mut self, pos: isize) -> u32 {
+        let index = self.index(pos);
+        self.cells[index]
+    }
+
+    fn set(&mut self, pos: isize, value: u32) {
+        let index = self.index(pos);
+        self.cells[index] = value;
+    }
+
+    /// read a cell without growing the tape, for read-only inspection;
+    /// cells outside the currently allocated range read as zero
+    fn peek(&self, pos: isize) -> u32 {
+        let signed_index = pos + self.base as isize;
+        if signed_index < 0 {
+            return 0;
+        }
+        *self.cells.get(signed_index as usize).unwrap_or(&0)
+    }
+}
+
+/// the bit width of a memory cell, selecting how far INCVAL/DECVAL wrap
+#[derive(PartialEq,Copy,Clone,Debug)]
+enum CellWidth {
+    Eight,
+    Sixteen,
+    ThirtyTwo,
+}
+
+impl CellWidth {
+    fn mask(self) -> u32 {
+        match self {
+            CellWidth::Eight => 0xFF,
+            CellWidth::Sixteen => 0xFFFF,
+            CellWidth::ThirtyTwo => 0xFFFFFFFF,
+        }
+    }
+
+    fn from_str(s: &str) -> Result<CellWidth, String> {
+        match s {
+            "8" => Ok(CellWidth::Eight),
+            "16" => Ok(CellWidth::Sixteen),
+            "32" => Ok(CellWidth::ThirtyTwo),
+            _ => Err(format!("Unknown cell width: {} (expected 8, 16 or 32)", s)),
+        }
+    }
+}
+
+/// what `,` does to the current cell once the input is exhausted
+#[derive(PartialEq,Copy,Clone,Debug)]
+enum EofPolicy {
+    Unchanged,
+    Zero,
+    AllOnes,
+}
+
+impl EofPolicy {
+    fn from_str(s: &str) -> Result<EofPolicy, String> {
+        match s {
+            "unchanged" => Ok(EofPolicy::Unchanged),
+            "zero" => Ok(EofPolicy::Zero),
+            "minus-one" => Ok(EofPolicy::AllOnes),
+            _ => Err(format!("Unknown EOF policy: {} (expected unchanged, zero or minus-one)", s)),
+        }
+    }
+}
+
+/// selects the dialect the interpreter should behave like: how wide a
+/// cell is and what `,` does once the input runs out
+#[derive(PartialEq,Copy,Clone,Debug)]
+struct MemoryConfig {
+    cell_width: CellWidth,
+    eof_policy: EofPolicy,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> MemoryConfig {
+        MemoryConfig { cell_width: CellWidth::Eight, eof_policy: EofPolicy::Unchanged }
+    }
 }
 
 struct Program {
     commands: Vec<Command>,
+    debug: bool,
 }
 
 impl Program {
-    pub fn run(&mut self, input: &mut dyn io::Read, output: &mut dyn io::Write) -> Result<(), String> {
-        let mut memory : HashMap<isize, u8> = HashMap::new();
+    /// `input` serves both the program's own `,`/GETC reads and the debugger
+    /// prompt; both need to share a single buffered view of the same stream
+    /// (e.g. one `BufReader<Stdin>`), since two independent buffers over the
+    /// same fd would each greedily swallow bytes meant for the other.
+    pub fn run(
+        &mut self,
+        input: &mut dyn io::BufRead,
+        output: &mut dyn io::Write,
+        config: MemoryConfig,
+        debug_output: &mut dyn io::Write,
+    ) -> Result<(), String> {
+        let mask = config.cell_width.mask();
+        let mut memory = Tape::new();
         let mut pos : isize = 0;
         let mut pc : usize = 0;
+        let mut stepping = false;
         loop {
             if pc >= self.commands.len() {
                 break;
             }
-            match self.commands[pc] {
+            let command = self.commands[pc];
+            if self.debug {
+                if let Command::BREAK = command {
+                    stepping = true;
+                }
+                if stepping && debug_prompt(pc, command, pos, &memory, input, debug_output) {
+                    stepping = false;
+                }
+            }
+            match command {
                 Command::INCPTR { amount } => pos = pos.checked_add(amount).ok_or("Pointer overflow")?,
                 Command::DECPTR { amount } => pos = pos.checked_sub(amount).ok_or("Pointer underflow")?,
                 Command::INCVAL { amount } => {
-                    let val = memory.entry(pos).or_insert(0);
-                    *val = val.wrapping_add(amount);
+                    let val = memory.get(pos);
+                    memory.set(pos, val.wrapping_add(amount) & mask);
                 },
                 Command::DECVAL { amount } => {
-                    let val = memory.entry(pos).or_insert(0);
-                    *val = val.wrapping_sub(amount);
+                    let val = memory.get(pos);
+                    memory.set(pos, val.wrapping_sub(amount) & mask);
                 },
                 Command::PUTC => {
-                    let char_out = *memory.get(&pos).unwrap_or(&0);
-                    output.write(&[char_out]).or(Err("Writing to output failed"))?;
+                    let char_out = memory.get(pos);
+                    output.write(&[char_out as u8]).or(Err("Writing to output failed"))?;
                 },
                 Command::GETC => {
                     let mut char_in = [0];
                     match input.read_exact(&mut char_in) {
-                        Ok(_) => memory.insert(pos, char_in[0]),
-                        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => None /* do nothing */,
+                        Ok(_) => memory.set(pos, char_in[0] as u32),
+                        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                            match config.eof_policy {
+                                EofPolicy::Unchanged => (),
+                                EofPolicy::Zero => memory.set(pos, 0),
+                                EofPolicy::AllOnes => memory.set(pos, mask),
+                            }
+                        },
                         Err(_) => return Err("Reading from input failed".to_string()),
                     };
                 },
                 Command::LOOPSTART { end } => {
-                    if *memory.get(&pos).unwrap_or(&0) == 0 {
+                    if memory.get(pos) == 0 {
                         pc = end;
                     }
                 },
@@ -65,6 +208,15 @@ impl Program {
                     pc = start;
                     continue;
                 },
+                Command::SETVAL { amount } => memory.set(pos, amount as u32),
+                Command::MULADD { offset, factor } => {
+                    let target = pos.checked_add(offset).ok_or("Pointer overflow")?;
+                    let current = memory.get(pos);
+                    let factor = (factor as i64).rem_euclid(mask as i64 + 1) as u32;
+                    let existing = memory.get(target);
+                    memory.set(target, existing.wrapping_add(current.wrapping_mul(factor)) & mask);
+                },
+                Command::BREAK => {},
             };
             pc = pc.checked_add(1).ok_or("PC overflow")?;
         }
@@ -72,6 +224,49 @@ impl Program {
     }
 }
 
+/// print a window of tape cells centered on `pos`, marking the current cell
+fn print_memory_window(pos: isize, memory: &Tape, out: &mut dyn io::Write) {
+    let radius = 5;
+    let _ = write!(out, "tape:");
+    for offset in -radius..=radius {
+        let marker = if offset == 0 { "*" } else { "" };
+        let _ = write!(out, " [{}{}:{}]", marker, pos + offset, memory.peek(pos + offset));
+    }
+    let _ = writeln!(out);
+}
+
+/// show the current step and prompt for a debugger command, reading from
+/// `debug_input` and writing to `debug_output` (mirroring how `Program::run`
+/// takes its program `input`/`output` rather than hardcoding stdio); returns
+/// true once the user asks to continue, false to single-step again
+fn debug_prompt(
+    pc: usize,
+    command: Command,
+    pos: isize,
+    memory: &Tape,
+    debug_input: &mut dyn io::BufRead,
+    debug_output: &mut dyn io::Write,
+) -> bool {
+    loop {
+        let _ = writeln!(debug_output, "pc={} cmd={:?} pos={}", pc, command, pos);
+        let _ = write!(debug_output, "(s)tep (c)ontinue (m)emory (p)ointer > ");
+        if debug_output.flush().is_err() {
+            return true;
+        }
+        let mut line = String::new();
+        if debug_input.read_line(&mut line).unwrap_or(0) == 0 {
+            return true; // debug input closed: just let the program run to completion
+        }
+        match line.trim() {
+            "s" | "step" => return false,
+            "c" | "continue" => return true,
+            "m" | "memory" => print_memory_window(pos, memory, debug_output),
+            "p" | "pointer" => { let _ = writeln!(debug_output, "pointer = {}", pos); },
+            other => { let _ = writeln!(debug_output, "unknown command: {}", other); },
+        }
+    }
+}
+
 /// read the program from the specified file into a string
 fn read_program(filename: &str) -> Result<String, io::Error> {
     let file = File::open(filename)?;
@@ -148,17 +343,226 @@ fn optimize_sequences(program: &mut Vec<Command>) {
     }
 }
 
+/// replace `[-]` and `[+]` loops with a single `SETVAL { amount: 0 }`,
+/// since both idioms just drive the current cell to zero
+fn optimize_clear_loops(program: &mut Vec<Command>) {
+    let mut i = 0;
+    while i + 2 < program.len() {
+        let is_clear_body = match program[i+1] {
+            Command::INCVAL { amount } => amount == 1,
+            Command::DECVAL { amount } => amount == 1,
+            _ => false,
+        };
+        if matches!(program[i], Command::LOOPSTART { .. }) &&
+           is_clear_body &&
+           matches!(program[i+2], Command::LOOPEND { .. }) {
+            program.splice(i..=i+2, [Command::SETVAL { amount: 0 }]);
+        }
+        i += 1;
+    }
+}
+
+/// find the command index of the `LOOPEND` matching the `LOOPSTART` at `start`
+fn find_matching_loop_end(program: &[Command], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for i in start..program.len() {
+        match program[i] {
+            Command::LOOPSTART { .. } => depth += 1,
+            Command::LOOPEND { .. } => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// add `delta` to the running total tracked for `offset`
+fn add_offset_delta(deltas: &mut Vec<(isize, i32)>, offset: isize, delta: i32) {
+    match deltas.iter_mut().find(|(o, _)| *o == offset) {
+        Some((_, total)) => *total += delta,
+        None => deltas.push((offset, delta)),
+    }
+}
+
+/// if `body` is a copy/multiply loop (net pointer movement of zero, current
+/// cell decremented by exactly one per iteration, no I/O or nested loops),
+/// return the `MULADD`/`SETVAL` commands that replace it
+fn muladd_replacement(body: &[Command]) -> Option<Vec<Command>> {
+    let mut pos: isize = 0;
+    let mut deltas: Vec<(isize, i32)> = Vec::new();
+    for command in body {
+        match *command {
+            Command::INCPTR { amount } => pos += amount,
+            Command::DECPTR { amount } => pos -= amount,
+            Command::INCVAL { amount } => add_offset_delta(&mut deltas, pos, amount as i32),
+            Command::DECVAL { amount } => add_offset_delta(&mut deltas, pos, -(amount as i32)),
+            _ => return None, // I/O or a nested loop: not a simple arithmetic loop
+        }
+    }
+    if pos != 0 {
+        return None;
+    }
+    let current_cell_delta = deltas.iter().find(|(o, _)| *o == 0).map_or(0, |(_, d)| *d);
+    if current_cell_delta != -1 {
+        return None;
+    }
+
+    let mut replacement: Vec<Command> = deltas.iter()
+        .filter(|(offset, _)| *offset != 0)
+        .map(|(offset, factor)| Command::MULADD { offset: *offset, factor: *factor })
+        .collect();
+    replacement.push(Command::SETVAL { amount: 0 });
+    Some(replacement)
+}
+
+/// replace copy/multiply loops like `[->+<]` or `[->++>+++<<]` with direct
+/// `MULADD` commands, so they execute in O(1) instead of looping
+fn optimize_muladd_loops(program: &mut Vec<Command>) {
+    let mut i = 0;
+    while i < program.len() {
+        if let Command::LOOPSTART { .. } = program[i] {
+            if let Some(end) = find_matching_loop_end(program, i) {
+                if let Some(replacement) = muladd_replacement(&program[i+1..end]) {
+                    program.splice(i..=end, replacement);
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+}
+
 fn optimize(program: &mut Vec<Command>) {
     optimize_cancelling_pairs(program);
     optimize_sequences(program);
+    optimize_clear_loops(program);
+    optimize_muladd_loops(program);
 }
 
 /// filter out all non-syntax characters from the input
 fn preprocess(program: &str) -> String {
-    let allowed_chars = ['>', '<', '+', '-', '.', ',', '[', ']'];
+    let allowed_chars = ['>', '<', '+', '-', '.', ',', '[', ']', '#'];
     program.chars().filter(|c| allowed_chars.contains(c)).collect()
 }
 
+/// emit the commands in `commands[start..end]` as C/Rust-like statements,
+/// recursing into loop bodies via their resolved `start`/`end` indices;
+/// `stmt` renders a single non-loop command and `loop_header` wraps a
+/// condition check around the body emitted for a `LOOPSTART`
+fn transpile_body(
+    commands: &[Command],
+    start: usize,
+    end: usize,
+    indent: usize,
+    stmt: &dyn Fn(Command, usize) -> String,
+    loop_header: &dyn Fn(usize) -> (String, String),
+) -> String {
+    let mut out = String::new();
+    let mut pc = start;
+    while pc < end {
+        match commands[pc] {
+            Command::LOOPSTART { end: loop_end } => {
+                let (open, close) = loop_header(indent);
+                out.push_str(&open);
+                out.push_str(&transpile_body(commands, pc + 1, loop_end, indent + 1, stmt, loop_header));
+                out.push_str(&close);
+                pc = loop_end;
+            },
+            Command::LOOPEND { .. } => {},
+            command => out.push_str(&stmt(command, indent)),
+        }
+        pc += 1;
+    }
+    out
+}
+
+/// lower the optimized command list into a standalone C program.
+/// Note: unlike the interpreter's `Tape`, which grows in both directions
+/// as needed, the generated tape is a fixed, zero-anchored 30000-byte
+/// array; a program that moves the pointer before the origin or past
+/// cell 30000 is undefined behavior here even though it runs fine under
+/// the interpreter. Only the default 8-bit/unchanged-EOF dialect is
+/// supported (see `MemoryConfig`).
+fn transpile_c(commands: &[Command]) -> String {
+    let pad = |indent: usize| "    ".repeat(indent);
+    let stmt = |command: Command, indent: usize| -> String {
+        let p = pad(indent);
+        match command {
+            Command::INCPTR { amount } => format!("{}p += {};\n", p, amount),
+            Command::DECPTR { amount } => format!("{}p -= {};\n", p, amount),
+            Command::INCVAL { amount } => format!("{}*p += {};\n", p, amount),
+            Command::DECVAL { amount } => format!("{}*p -= {};\n", p, amount),
+            Command::PUTC => format!("{}putchar(*p);\n", p),
+            Command::GETC => format!("{}{{ int c = getchar(); if (c != EOF) *p = (char)c; }}\n", p),
+            Command::SETVAL { amount } => format!("{}*p = {};\n", p, amount),
+            Command::MULADD { offset, factor } => format!("{}p[{}] += *p * {};\n", p, offset, factor),
+            Command::BREAK => String::new(),
+            Command::LOOPSTART { .. } | Command::LOOPEND { .. } => unreachable!(),
+        }
+    };
+    let loop_header = |indent: usize| -> (String, String) {
+        let p = pad(indent);
+        (format!("{}while (*p) {{\n", p), format!("{}}}\n", p))
+    };
+
+    let mut out = String::new();
+    out.push_str("#include <stdio.h>\n\n");
+    out.push_str("int main(void) {\n");
+    out.push_str("    static char tape[30000];\n");
+    out.push_str("    char *p = tape;\n\n");
+    out.push_str(&transpile_body(commands, 0, commands.len(), 1, &stmt, &loop_header));
+    out.push_str("\n    return 0;\n}\n");
+    out
+}
+
+/// lower the optimized command list into a standalone Rust program.
+/// Note: unlike the interpreter's `Tape`, which grows in both directions
+/// as needed, the generated tape is a fixed, zero-anchored 30000-byte
+/// array; a program that moves the pointer before the origin or past
+/// cell 30000 panics here even though it runs fine under the
+/// interpreter. Only the default 8-bit/unchanged-EOF dialect is
+/// supported (see `MemoryConfig`).
+fn transpile_rust(commands: &[Command]) -> String {
+    let pad = |indent: usize| "    ".repeat(indent);
+    let stmt = |command: Command, indent: usize| -> String {
+        let p = pad(indent);
+        match command {
+            Command::INCPTR { amount } => format!("{}p += {};\n", p, amount),
+            Command::DECPTR { amount } => format!("{}p -= {};\n", p, amount),
+            Command::INCVAL { amount } => format!("{}tape[p] = tape[p].wrapping_add({});\n", p, (amount % 256) as u8),
+            Command::DECVAL { amount } => format!("{}tape[p] = tape[p].wrapping_sub({});\n", p, (amount % 256) as u8),
+            Command::PUTC => format!("{}stdout.write_all(&[tape[p]]).unwrap();\n", p),
+            Command::GETC => format!("{}stdin.read_exact(&mut tape[p..p+1]).unwrap_or(());\n", p),
+            Command::SETVAL { amount } => format!("{}tape[p] = {};\n", p, amount),
+            Command::MULADD { offset, factor } => format!(
+                "{}let target = (p as isize + {}) as usize;\n{}tape[target] = tape[target].wrapping_add(tape[p].wrapping_mul({}));\n",
+                p, offset, p, factor.rem_euclid(256) as u8,
+            ),
+            Command::BREAK => String::new(),
+            Command::LOOPSTART { .. } | Command::LOOPEND { .. } => unreachable!(),
+        }
+    };
+    let loop_header = |indent: usize| -> (String, String) {
+        let p = pad(indent);
+        (format!("{}while tape[p] != 0 {{\n", p), format!("{}}}\n", p))
+    };
+
+    let mut out = String::new();
+    out.push_str("use std::io::{self, Read, Write};\n\n");
+    out.push_str("fn main() {\n");
+    out.push_str("    let mut tape = [0u8; 30000];\n");
+    out.push_str("    let mut p: usize = 0;\n");
+    out.push_str("    let mut stdin = io::stdin();\n");
+    out.push_str("    let mut stdout = io::stdout();\n\n");
+    out.push_str(&transpile_body(commands, 0, commands.len(), 1, &stmt, &loop_header));
+    out.push_str("}\n");
+    out
+}
+
 /// convert input string into syntax tokens
 fn tokenize(input: &str) -> Vec<Command> {
     input.chars().map(|token|
@@ -171,17 +575,61 @@ fn tokenize(input: &str) -> Vec<Command> {
             ',' => Command::GETC,
             '[' => Command::LOOPSTART { end: 0 },
             ']' => Command::LOOPEND { start: 0 },
+            '#' => Command::BREAK,
             _ => panic!("Trying to tokenize invalid character: {}", token),
         }
     ).collect()
 }
 
+/// options parsed from the command line
+struct CliOptions<'a> {
+    emit: Option<String>,
+    debug: bool,
+    memory_config: MemoryConfig,
+    filename: &'a String,
+}
+
+/// parse `--emit c|rust`, `--debug`, `--cell-width`, `--eof-policy` and the
+/// program filename out of the CLI arguments
+fn parse_args(args: &[String]) -> Result<CliOptions<'_>, String> {
+    let mut emit = None;
+    let mut debug = false;
+    let mut memory_config = MemoryConfig::default();
+    let mut filename = None;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--emit" => {
+                i += 1;
+                emit = Some(args.get(i).ok_or("--emit requires an argument: c or rust")?.clone());
+            },
+            "--debug" => debug = true,
+            "--cell-width" => {
+                i += 1;
+                let width = args.get(i).ok_or("--cell-width requires an argument: 8, 16 or 32")?;
+                memory_config.cell_width = CellWidth::from_str(width)?;
+            },
+            "--eof-policy" => {
+                i += 1;
+                let policy = args.get(i).ok_or("--eof-policy requires an argument: unchanged, zero or minus-one")?;
+                memory_config.eof_policy = EofPolicy::from_str(policy)?;
+            },
+            _ => filename = Some(&args[i]),
+        }
+        i += 1;
+    }
+    let filename = filename.ok_or_else(|| format!(
+        "Usage: {} [--emit c|rust] [--debug] [--cell-width 8|16|32] [--eof-policy unchanged|zero|minus-one] filename",
+        args[0],
+    ))?;
+    Ok(CliOptions { emit, debug, memory_config, filename })
+}
+
 fn run() -> Result<(), String> {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        return Err(format!("Usage: {} filename", args[0]));
-    }
-    let input = match read_program(&args[1]) {
+    let options = parse_args(&args)?;
+
+    let input = match read_program(options.filename) {
         Ok(p) => preprocess(&p),
         Err(e) => return Err(format!("Cannot open file: {}", e)),
     };
@@ -189,8 +637,27 @@ fn run() -> Result<(), String> {
     optimize(&mut commands);
     find_loops(&mut commands)?;
 
-    let mut program = Program{commands};
-    program.run(&mut io::stdin(), &mut io::stdout())
+    if options.emit.is_some() && (options.debug || options.memory_config != MemoryConfig::default()) {
+        return Err("--emit only supports the default 8-bit/unchanged-EOF dialect; \
+                     --debug, --cell-width and --eof-policy require the interpreter".to_string());
+    }
+
+    match options.emit.as_deref() {
+        Some("c") => {
+            print!("{}", transpile_c(&commands));
+            Ok(())
+        },
+        Some("rust") => {
+            print!("{}", transpile_rust(&commands));
+            Ok(())
+        },
+        Some(target) => Err(format!("Unknown --emit target: {}", target)),
+        None => {
+            let mut program = Program{commands, debug: options.debug};
+            let mut stdin = io::BufReader::new(io::stdin());
+            program.run(&mut stdin, &mut io::stdout(), options.memory_config, &mut io::stdout())
+        },
+    }
 }
 
 fn main() {
@@ -280,13 +747,188 @@ mod tests {
         let code = "++[>[>],+[<]>-]>[.>]";  // reads 2 chars, increments them, and prints them at the end
         let mut commands = tokenize(code);
         find_loops(&mut commands).unwrap();
-        let mut program = Program{commands};
-        program.run(&mut buf_in, &mut buf_out).unwrap();
+        let mut program = Program{commands, debug: false};
+        program.run(&mut buf_in, &mut buf_out, MemoryConfig::default(), &mut io::sink()).unwrap();
 
         let expected = vec!['4' as u8, '2' as u8];
         assert_eq!(buf_out.get_ref(), &expected);
     }
 
+    #[test]
+    fn test_debug_step_continue() {
+        use std::io::Cursor;
+
+        // # sets a breakpoint, then "+++." increments the cell three times
+        // and prints it; a scripted debug session steps once past the
+        // breakpoint, then continues and lets the rest run uninterrupted
+        let code = "#+++.";
+        let mut commands = tokenize(code);
+        find_loops(&mut commands).unwrap();
+        let mut program = Program{commands, debug: true};
+
+        // program and debugger prompts share one input stream, same as the
+        // CLI shares one BufReader<Stdin> between them
+        let mut input = Cursor::new("s\nc\n".as_bytes());
+        let mut buf_out = Cursor::new(Vec::new());
+        let mut debug_out = Cursor::new(Vec::new());
+        program.run(&mut input, &mut buf_out, MemoryConfig::default(), &mut debug_out).unwrap();
+
+        assert_eq!(buf_out.get_ref(), &vec![3u8]);
+
+        let transcript = String::from_utf8(debug_out.into_inner()).unwrap();
+        assert!(transcript.contains("pc=0 cmd=BREAK"));
+        assert!(transcript.contains("pc=1 cmd=INCVAL"));
+        assert!(!transcript.contains("pc=2"));
+    }
+
+    #[test]
+    fn test_debug_shares_input_stream_with_getc() {
+        use std::io::Cursor;
+
+        // debugger commands and program input ("," reads) are interleaved
+        // in a single stream, as they are when both come from a real,
+        // piped stdin; a private buffer for either consumer would swallow
+        // bytes meant for the other
+        let code = "#,.#,.";
+        let mut commands = tokenize(code);
+        find_loops(&mut commands).unwrap();
+        let mut program = Program{commands, debug: true};
+
+        // "c\n" continues past each breakpoint; the raw bytes 'A' and 'B'
+        // (no trailing newline) are what "," should read, not a debug command
+        let mut input = Cursor::new("c\nAc\nB".as_bytes());
+        let mut buf_out = Cursor::new(Vec::new());
+        let mut debug_out = Cursor::new(Vec::new());
+        program.run(&mut input, &mut buf_out, MemoryConfig::default(), &mut debug_out).unwrap();
+
+        assert_eq!(buf_out.get_ref(), &vec!['A' as u8, 'B' as u8]);
+    }
+
+    #[test]
+    fn test_cell_width_from_str() {
+        assert_eq!(CellWidth::from_str("8").unwrap(), CellWidth::Eight);
+        assert_eq!(CellWidth::from_str("16").unwrap(), CellWidth::Sixteen);
+        assert_eq!(CellWidth::from_str("32").unwrap(), CellWidth::ThirtyTwo);
+        assert!(CellWidth::from_str("64").is_err());
+    }
+
+    #[test]
+    fn test_eof_policy_from_str() {
+        assert_eq!(EofPolicy::from_str("unchanged").unwrap(), EofPolicy::Unchanged);
+        assert_eq!(EofPolicy::from_str("zero").unwrap(), EofPolicy::Zero);
+        assert_eq!(EofPolicy::from_str("minus-one").unwrap(), EofPolicy::AllOnes);
+        assert!(EofPolicy::from_str("bogus").is_err());
+    }
+
+    #[test]
+    fn test_memory_config_cell_width_wrapping() {
+        use std::io::Cursor;
+
+        // 300 increments then 44 decrements: wraps to 0 at 8-bit width
+        // (skipping the loop), but lands on the nonzero value 256 at
+        // 16-bit width (one pass through the loop, printing its low byte)
+        let code = format!("{}{}[.[-]]", "+".repeat(300), "-".repeat(44));
+        let mut commands = tokenize(&code);
+        optimize(&mut commands);
+        find_loops(&mut commands).unwrap();
+
+        let mut program = Program{commands: commands.clone(), debug: false};
+        let mut buf_in = Cursor::new(Vec::new());
+        let mut buf_out = Cursor::new(Vec::new());
+        let config = MemoryConfig { cell_width: CellWidth::Eight, ..MemoryConfig::default() };
+        program.run(&mut buf_in, &mut buf_out, config, &mut io::sink()).unwrap();
+        assert!(buf_out.get_ref().is_empty());
+
+        let mut program = Program{commands, debug: false};
+        let mut buf_in = Cursor::new(Vec::new());
+        let mut buf_out = Cursor::new(Vec::new());
+        let config = MemoryConfig { cell_width: CellWidth::Sixteen, ..MemoryConfig::default() };
+        program.run(&mut buf_in, &mut buf_out, config, &mut io::sink()).unwrap();
+        assert_eq!(buf_out.get_ref(), &vec![0]);
+    }
+
+    #[test]
+    fn test_memory_config_eof_policy() {
+        use std::io::Cursor;
+
+        let policies = [
+            (EofPolicy::Unchanged, 5u8),
+            (EofPolicy::Zero, 0u8),
+            (EofPolicy::AllOnes, 255u8),
+        ];
+        for (policy, expected) in policies {
+            let mut commands = tokenize("+++++,.");
+            find_loops(&mut commands).unwrap();
+            let mut program = Program{commands, debug: false};
+            let mut buf_in = Cursor::new(Vec::new()); // empty: GETC hits EOF immediately
+            let mut buf_out = Cursor::new(Vec::new());
+            let config = MemoryConfig { cell_width: CellWidth::Eight, eof_policy: policy };
+            program.run(&mut buf_in, &mut buf_out, config, &mut io::sink()).unwrap();
+            assert_eq!(buf_out.get_ref(), &vec![expected]);
+        }
+    }
+
+    #[test]
+    fn test_transpile_c() {
+        let mut commands = tokenize("[->+<]");
+        optimize_sequences(&mut commands);
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(transpile_c(&commands), "\
+#include <stdio.h>
+
+int main(void) {
+    static char tape[30000];
+    char *p = tape;
+
+    p[1] += *p * 1;
+    *p = 0;
+
+    return 0;
+}
+");
+    }
+
+    #[test]
+    fn test_transpile_c_getc_checks_eof() {
+        // GETC must leave the cell unchanged on EOF, matching the
+        // interpreter's default EofPolicy::Unchanged, rather than storing
+        // getchar()'s -1 sentinel straight into the cell
+        let commands = tokenize(",");
+        assert_eq!(transpile_c(&commands), "\
+#include <stdio.h>
+
+int main(void) {
+    static char tape[30000];
+    char *p = tape;
+
+    { int c = getchar(); if (c != EOF) *p = (char)c; }
+
+    return 0;
+}
+");
+    }
+
+    #[test]
+    fn test_transpile_rust() {
+        let mut commands = tokenize("[->+<]");
+        optimize_sequences(&mut commands);
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(transpile_rust(&commands), "\
+use std::io::{self, Read, Write};
+
+fn main() {
+    let mut tape = [0u8; 30000];
+    let mut p: usize = 0;
+    let mut stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    let target = (p as isize + 1) as usize;
+    tape[target] = tape[target].wrapping_add(tape[p].wrapping_mul(1));
+    tape[p] = 0;
+}
+");
+    }
+
     #[test]
     fn test_optimize_pair_removal() {
         let incv = Command::INCVAL { amount: 1 };
@@ -344,4 +986,76 @@ mod tests {
         optimize(&mut commands);
         assert_eq!(commands, [Command::INCVAL { amount: 3 }]);
     }
+
+    #[test]
+    fn test_optimize_clear_loops() {
+        let mut commands = tokenize("[-]");
+        optimize_clear_loops(&mut commands);
+        assert_eq!(commands, [Command::SETVAL { amount: 0 }]);
+
+        let mut commands = tokenize("[+]");
+        optimize_clear_loops(&mut commands);
+        assert_eq!(commands, [Command::SETVAL { amount: 0 }]);
+
+        // loop bodies that don't match the idiom are left alone
+        let mut commands = tokenize("[->+<]");
+        let expected = commands.clone();
+        optimize_clear_loops(&mut commands);
+        assert_eq!(commands, expected);
+    }
+
+    #[test]
+    fn test_optimize_muladd_loops() {
+        let mut commands = tokenize("[->+<]");
+        optimize_sequences(&mut commands);
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(commands, [
+            Command::MULADD { offset: 1, factor: 1 },
+            Command::SETVAL { amount: 0 },
+        ]);
+
+        let mut commands = tokenize("[->++>+++<<]");
+        optimize_sequences(&mut commands);
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(commands, [
+            Command::MULADD { offset: 1, factor: 2 },
+            Command::MULADD { offset: 2, factor: 3 },
+            Command::SETVAL { amount: 0 },
+        ]);
+
+        // a loop with I/O isn't a pure arithmetic loop
+        let mut commands = tokenize("[->+<.]");
+        let expected = commands.clone();
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(commands, expected);
+
+        // net pointer movement must be zero
+        let mut commands = tokenize("[->+<<]");
+        let expected = commands.clone();
+        optimize_muladd_loops(&mut commands);
+        assert_eq!(commands, expected);
+    }
+
+    /// manual timing harness for loop-heavy programs; run with
+    /// `cargo test -- --ignored --nocapture` to see the elapsed time
+    #[test]
+    #[ignore]
+    fn bench_tight_loop() {
+        use std::time::Instant;
+
+        // a counting loop with a PUTC in its body, so optimize_muladd_loops
+        // (which bails out on any I/O) can't fold it to a single MULADD;
+        // it actually runs its 50 iterations at runtime on every repeat,
+        // exercising INCPTR/DECPTR/INCVAL/DECVAL/PUTC and the loop
+        // condition check on every iteration
+        let code = format!("{}[>+.<-]", "+".repeat(50)).repeat(2000);
+        let mut commands = tokenize(&preprocess(&code));
+        optimize(&mut commands);
+        find_loops(&mut commands).unwrap();
+        let mut program = Program{commands, debug: false};
+
+        let start = Instant::now();
+        program.run(&mut io::empty(), &mut io::sink(), MemoryConfig::default(), &mut io::sink()).unwrap();
+        println!("tight loop took {:?}", start.elapsed());
+    }
 }